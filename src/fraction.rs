@@ -3,9 +3,10 @@
 use std::ops::{Mul, Add, Sub, Div};
 use std::convert::TryInto;
 use std::cmp::Ordering;
+use std::iter::Sum;
 
 /// (Unsigned) Fraction of a measure.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct Fraction {
     pub num: u8,
     pub den: u8,
@@ -21,6 +22,77 @@ impl Fraction {
     pub fn recip(self) -> Self {
         Self { num: self.den, den: self.num }
     }
+
+    /// Reduce to lowest terms.
+    pub fn reduce(self) -> Self {
+        let gcd = gcd_i(u32::from(self.num), u32::from(self.den));
+        if gcd == 0 {
+            return self;
+        }
+
+        Self {
+            num: (u32::from(self.num) / gcd) as u8,
+            den: (u32::from(self.den) / gcd) as u8,
+        }
+    }
+
+    /// Multiply, widening into `u32` to avoid intermediate overflow.
+    /// Returns `None` if the reduced result doesn't fit back into a `u8`.
+    pub fn checked_mul(self, other: Fraction) -> Option<Fraction> {
+        let num = u32::from(self.num) * u32::from(other.num);
+        let den = u32::from(self.den) * u32::from(other.den);
+
+        let gcd = gcd_i(num, den);
+        let (num, den) = if gcd == 0 { (num, den) } else { (num / gcd, den / gcd) };
+
+        Some(Fraction { num: num.try_into().ok()?, den: den.try_into().ok()? })
+    }
+
+    /// Add, widening into `u32` to avoid intermediate overflow.  Returns
+    /// `None` if the reduced result doesn't fit back into a `u8`.
+    pub fn checked_add(self, other: Fraction) -> Option<Fraction> {
+        let (self_mul, other_mul, den) = common_denominator(self.den, other.den);
+
+        let num = u32::from(self.num) * self_mul + u32::from(other.num) * other_mul;
+
+        let gcd = gcd_i(num, den);
+        let (num, den) = if gcd == 0 { (num, den) } else { (num / gcd, den / gcd) };
+
+        Some(Fraction { num: num.try_into().ok()?, den: den.try_into().ok()? })
+    }
+
+    /// Subtract, widening into `u32` to avoid intermediate overflow.
+    /// Returns `None` if `other` is larger than `self` (the unsigned result
+    /// would underflow), or if the reduced result doesn't fit into a `u8`.
+    pub fn checked_sub(self, other: Fraction) -> Option<Fraction> {
+        let (self_mul, other_mul, den) = common_denominator(self.den, other.den);
+
+        let num = (u32::from(self.num) * self_mul).checked_sub(u32::from(other.num) * other_mul)?;
+
+        let gcd = gcd_i(num, den);
+        let (num, den) = if gcd == 0 { (num, den) } else { (num / gcd, den / gcd) };
+
+        Some(Fraction { num: num.try_into().ok()?, den: den.try_into().ok()? })
+    }
+}
+
+/// Compute `(self_mul, other_mul, lcm)` such that
+/// `self_num * self_mul` and `other_num * other_mul` share `lcm` as their
+/// denominator.
+fn common_denominator(self_den: u8, other_den: u8) -> (u32, u32, u32) {
+    let self_den = u32::from(self_den);
+    let other_den = u32::from(other_den);
+
+    let gcd = gcd_i(self_den, other_den);
+    let lcm = self_den / gcd * other_den;
+
+    (lcm / self_den, lcm / other_den, lcm)
+}
+
+impl From<(u8, u8)> for Fraction {
+    fn from((num, den): (u8, u8)) -> Self {
+        Fraction::new(num, den)
+    }
 }
 
 impl Mul<i32> for Fraction {
@@ -37,20 +109,7 @@ impl Mul for Fraction {
     type Output = Fraction;
 
     fn mul(self, other: Fraction) -> Self::Output {
-        let mut num: u16 = self.num.into();
-        let mut den: u16 = self.den.into();
-        let other_num: u16 = other.num.into();
-        let other_den: u16 = other.den.into();
-
-        num *= other_num;
-        den *= other_den;
-
-        let gcd = gcd_i(num, den);
-
-        Fraction {
-            num: (num / gcd).try_into().unwrap_or(0),
-            den: (den / gcd).try_into().unwrap_or(0),
-        }
+        self.checked_mul(other).expect("Fraction multiplication overflowed")
     }
 }
 
@@ -66,20 +125,7 @@ impl Add for Fraction {
     type Output = Fraction;
 
     fn add(self, other: Fraction) -> Self::Output {
-        let (self_mul, other_mul, den) = if self.den % other.den == 0 {
-            (1, self.den / other.den, self.den)
-        } else if other.den % self.den == 0 {
-            (other.den / self.den, 1, other.den)
-        } else {
-            (other.den, self.den, self.den * other.den)
-        };
-
-        let num = self.num * self_mul + other.num * other_mul;
-        let gcd = gcd_i(num, den);
-        Fraction {
-            num: num / gcd,
-            den: den / gcd,
-        }
+        self.checked_add(other).expect("Fraction addition overflowed")
     }
 }
 
@@ -87,33 +133,36 @@ impl Sub for Fraction {
     type Output = Fraction;
 
     fn sub(self, other: Fraction) -> Self::Output {
-        let (self_mul, other_mul, den) = if self.den % other.den == 0 {
-            (1, self.den / other.den, self.den)
-        } else if other.den % self.den == 0 {
-            (other.den / self.den, 1, other.den)
-        } else {
-            (other.den, self.den, self.den * other.den)
-        };
-
-        let num = self.num * self_mul - other.num * other_mul;
-        let gcd = gcd_i(num, den);
-        Fraction {
-            num: num / gcd,
-            den: den / gcd,
-        }
+        self.checked_sub(other).expect("Fraction subtraction underflowed")
     }
 }
 
-impl PartialOrd for Fraction {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let den = gcd_i(self.den, other.den);
+impl Sum for Fraction {
+    fn sum<I: Iterator<Item = Fraction>>(iter: I) -> Self {
+        iter.fold(Fraction::new(0, 1), |acc, x| acc + x)
+    }
+}
 
-        let self_mul = (den / self.den) as i32;
-        let other_mul = (den / other.den) as i32;
+impl PartialEq for Fraction {
+    fn eq(&self, other: &Self) -> bool {
+        u32::from(self.num) * u32::from(other.den) == u32::from(other.num) * u32::from(self.den)
+    }
+}
+
+impl Eq for Fraction {}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = u32::from(self.num) * u32::from(other.den);
+        let rhs = u32::from(other.num) * u32::from(self.den);
 
-        let num = self.num as i32 * self_mul - other.num as i32 * other_mul;
+        lhs.cmp(&rhs)
+    }
+}
 
-        num.partial_cmp(&0)
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -195,6 +244,16 @@ mod tests {
         assert_eq!(Fraction::new(4, 4) + Fraction::new(2, 4), Fraction::new(3, 2));
     }
 
+    #[test]
+    fn add_does_not_overflow_u8() {
+        // 16 sixteenths: the raw `u8` product of two of the denominators
+        // (16 * 16) already overflows.
+        let sum: Fraction = std::iter::repeat(Fraction::new(1, 16)).take(16).sum();
+        assert_eq!(sum, Fraction::new(1, 1));
+
+        assert_eq!(Fraction::new(5, 7) + Fraction::new(6, 11), Fraction::new(97, 77));
+    }
+
     #[test]
     fn sub() {
         assert_eq!(Fraction::new(5, 4) - Fraction::new(1, 2), Fraction::new(3, 4));
@@ -210,4 +269,31 @@ mod tests {
     fn mul() {
         assert_eq!(Fraction::new(1, 2) * Fraction::new(3, 4), Fraction::new(3, 8));
     }
+
+    #[test]
+    fn ord() {
+        assert!(Fraction::new(1, 3) < Fraction::new(1, 2));
+        assert!(Fraction::new(3, 4) > Fraction::new(2, 3));
+        assert_eq!(Fraction::new(1, 2).cmp(&Fraction::new(2, 4)), Ordering::Equal);
+
+        let mut fractions = vec![Fraction::new(3, 4), Fraction::new(1, 8), Fraction::new(1, 2)];
+        fractions.sort();
+        assert_eq!(fractions, vec![Fraction::new(1, 8), Fraction::new(1, 2), Fraction::new(3, 4)]);
+    }
+
+    #[test]
+    fn value_equality_ignores_representation() {
+        assert_eq!(Fraction::new(1, 2), Fraction::new(2, 4));
+    }
+
+    #[test]
+    fn reduce() {
+        assert_eq!(Fraction::new(2, 4).reduce(), Fraction::new(1, 2));
+    }
+
+    #[test]
+    fn from_tuple() {
+        let f: Fraction = (3, 4).into();
+        assert_eq!(f, Fraction::new(3, 4));
+    }
 }
@@ -22,9 +22,18 @@ use std::str::FromStr;
 
 pub mod note;
 mod fraction;
+mod lilypond;
+mod maqam;
+mod midi;
+mod performance;
+mod render;
+mod unfold;
 
 pub use fraction::{Fraction, IsZero};
+pub use maqam::Alteration;
 pub use note::{Note, Articulation, PitchClass, PitchName, PitchAccidental, PitchOctave, Duration};
+pub use performance::{perform, Event};
+pub use render::{render_movement, DEFAULT_SAMPLE_RATE};
 
 /// Cursor pointing to a marking
 #[derive(Clone, Default, Debug, PartialEq)]
@@ -101,6 +110,66 @@ pub enum Dynamic {
     SFP,
 }
 
+impl Dynamic {
+    /// MIDI-style velocity (0-127) for this dynamic level (pp≈33, fff≈120).
+    pub(crate) fn velocity(&self) -> u8 {
+        use Dynamic::*;
+
+        match self {
+            PPPPPP => 8,
+            PPPPP => 16,
+            PPPP => 24,
+            PPP => 28,
+            PP => 33,
+            P => 42,
+            MP => 53,
+            MF => 64,
+            F => 80,
+            FF => 96,
+            FFF => 120,
+            FFFF => 124,
+            FFFFF => 126,
+            FFFFFF => 127,
+            N => 1,
+            SF => 112,
+            SFZ => 127,
+            FP => 96,
+            SFP => 112,
+        }
+    }
+}
+
+impl FromStr for Dynamic {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Dynamic::*;
+
+        Ok(match s {
+            "pppppp" => PPPPPP,
+            "ppppp" => PPPPP,
+            "pppp" => PPPP,
+            "ppp" => PPP,
+            "pp" => PP,
+            "p" => P,
+            "mp" => MP,
+            "mf" => MF,
+            "f" => F,
+            "ff" => FF,
+            "fff" => FFF,
+            "ffff" => FFFF,
+            "fffff" => FFFFF,
+            "ffffff" => FFFFFF,
+            "n" => N,
+            "sf" => SF,
+            "sfz" => SFZ,
+            "fp" => FP,
+            "sfp" => SFP,
+            _ => return Err(()),
+        })
+    }
+}
+
 /// A marking.
 pub enum Marking {
     /// Change intensity of sound.
@@ -137,11 +206,26 @@ impl FromStr for Marking {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Marking::Note(s.parse::<Note>()?))
+        Ok(match s {
+            "<" => Marking::Cresc,
+            ">" => Marking::Dim,
+            "breath" => Marking::Breath,
+            "caesura" => Marking::CaesuraShort,
+            "grandpause" => Marking::CaesuraLong,
+            "pizz" => Marking::Pizz,
+            "arco" => Marking::Arco,
+            "mute" => Marking::Mute,
+            "open" => Marking::Open,
+            _ => match s.parse::<Dynamic>() {
+                Ok(dynamic) => Marking::Dynamic(dynamic),
+                Err(_) => Marking::Note(s.parse::<Note>()?),
+            },
+        })
     }
 }
 
 /// A repeat marking for a measure.
+#[derive(Copy, Clone)]
 pub enum Repeat {
     /// Repeat sign open ||:
     Open,
@@ -163,6 +247,40 @@ pub enum Repeat {
     Ending(u8),
 }
 
+impl FromStr for Repeat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "|:" => Repeat::Open,
+            ":|" => Repeat::Close,
+            "segno" => Repeat::Segno,
+            "dc" => Repeat::DC,
+            "ds" => Repeat::DS,
+            "coda" => Repeat::Coda,
+            "tocoda" => Repeat::ToCoda,
+            "fine" => Repeat::Fine,
+            _ => Repeat::Ending(s.parse().map_err(|_| ())?),
+        })
+    }
+}
+
+impl fmt::Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Repeat::Open => write!(f, "|:"),
+            Repeat::Close => write!(f, ":|"),
+            Repeat::Segno => write!(f, "segno"),
+            Repeat::DC => write!(f, "dc"),
+            Repeat::DS => write!(f, "ds"),
+            Repeat::Coda => write!(f, "coda"),
+            Repeat::ToCoda => write!(f, "tocoda"),
+            Repeat::Fine => write!(f, "fine"),
+            Repeat::Ending(n) => write!(f, "{}", n),
+        }
+    }
+}
+
 /////////////////////
 ////             ////
 /////////////////////
@@ -220,6 +338,22 @@ pub struct Sig {
     pub swing: Option<u8>,
 }
 
+impl Sig {
+    /// Ordered `(pitch-step, alteration)` pairs making up this key's
+    /// signature: standard 12-tone key signatures for `key` 0-23, maqam/makam
+    /// key signatures for `key` 24+.
+    pub fn key_signature(&self) -> Vec<(PitchName, Alteration)> {
+        maqam::key_signature(self.key)
+    }
+
+    /// The accidental `name` should sound as under this key signature, so
+    /// rendering/synthesis can tune notes (a quarter tone is a frequency
+    /// offset of 2^(1/24)), or `None` if the key signature doesn't alter it.
+    pub fn effective_accidental(&self, name: PitchName) -> Option<PitchAccidental> {
+        maqam::effective_accidental(self.key, name)
+    }
+}
+
 /// Channel information for a specific bar of music.
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
 pub struct Chan {
@@ -294,6 +428,15 @@ pub struct Instrument {
     ff: Option<String>,
     // Use different waveform for this dynamic
     fff: Option<String>,
+
+    // Envelope attack time in seconds (default=0.01).
+    attack: Option<f32>,
+    // Envelope decay time in seconds (default=0.08).
+    decay: Option<f32>,
+    // Envelope sustain level, 0-1 (default=0.75).
+    sustain: Option<f32>,
+    // Envelope release time in seconds (default=0.15).
+    release: Option<f32>,
 }
 
 /*/// A soundfont used in the score (either in the .scof or a .sfsf and linked to).
@@ -535,4 +678,32 @@ impl Scof {
         let m = self.marking_str_mut(0, cursor).unwrap();
         *m = note.to_string();
     }
+
+    /// Render the first movement to 16-bit PCM samples at `sample_rate`,
+    /// assuming A4 = 440 Hz.
+    pub fn render(&self, sample_rate: u32) -> Vec<i16> {
+        match self.movement.get(0) {
+            Some(movement) => render_movement(movement, &self.synth, &self.soundfont, 440.0, sample_rate),
+            None => vec![],
+        }
+    }
+
+    /// Serialize this score as LilyPond source.
+    pub fn to_lilypond(&self) -> String {
+        lilypond::to_lilypond(self)
+    }
+
+    /// Serialize this score as a Type-1 Standard MIDI File.
+    pub fn to_midi(&self) -> Vec<u8> {
+        midi::to_midi(self)
+    }
+
+    /// Resolve the first movement into a flat, timed list of performed
+    /// events (dynamics, cresc/dim, swing, and grace notes all folded in).
+    pub fn perform(&self) -> Vec<performance::Event> {
+        match self.movement.get(0) {
+            Some(movement) => performance::perform(movement),
+            None => vec![],
+        }
+    }
 }
@@ -0,0 +1,265 @@
+//! LilyPond (.ly) export of a [`Scof`].
+//!
+//! Each [`Movement`] becomes a `\score` block, each channel in its bars
+//! becomes a `\new Staff` voice.  `Marking::from_str` produces notes,
+//! dynamics, cresc/dim, breath and caesura marks, and pizz/arco/mute/open;
+//! it never produces `GraceInto`/`GraceOutOf` or `Marking::Repeat`, which
+//! are mapped below only for completeness.  Bar-level `Repeat` tokens come
+//! from `Bar::repeat`.
+
+use std::fmt::Write;
+use crate::{Bar, Chan, Dynamic, Fraction, Marking, Movement, Note, PitchAccidental, PitchName, Repeat, Scof, Sig};
+
+pub fn to_lilypond(scof: &Scof) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "\\version \"2.20.0\"").unwrap();
+    writeln!(out, "% {}", scof.title).unwrap();
+    writeln!(out).unwrap();
+
+    for movement in &scof.movement {
+        write_movement(&mut out, movement);
+    }
+
+    out
+}
+
+fn write_movement(out: &mut String, movement: &Movement) {
+    let num_chans = movement.bar.get(0).map(|bar| bar.chan.len()).unwrap_or(0);
+
+    writeln!(out, "\\score {{").unwrap();
+    writeln!(out, "  \\new StaffGroup <<").unwrap();
+
+    for c in 0..num_chans {
+        writeln!(out, "    \\new Staff {{ \\new Voice {{").unwrap();
+        write_channel(out, movement, c);
+        writeln!(out, "    }} }}").unwrap();
+    }
+
+    writeln!(out, "  >>").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+}
+
+fn write_channel(out: &mut String, movement: &Movement, c: usize) {
+    let mut current_sig = 0usize;
+
+    for bar in &movement.bar {
+        if let Some(sig_index) = bar.sig {
+            current_sig = sig_index as usize;
+        }
+        if bar.sig.is_some() {
+            if let Some(sig) = movement.sig.get(current_sig) {
+                write_sig(out, sig);
+            }
+        }
+
+        for repeat in pre_repeats(bar) {
+            writeln!(out, "      {}", repeat_prefix(&repeat)).unwrap();
+        }
+
+        if let Some(chan) = bar.chan.get(c) {
+            write!(out, "      ").unwrap();
+            for note_str in notes(chan) {
+                let marking: Marking = match note_str.parse() {
+                    Ok(marking) => marking,
+                    Err(_) => continue,
+                };
+                write!(out, "{} ", marking_to_lilypond(&marking)).unwrap();
+            }
+            writeln!(out, "|").unwrap();
+        }
+
+        if has_close(bar) {
+            writeln!(out, "      }}").unwrap();
+        }
+    }
+}
+
+/// Repeat tokens for a bar that open a section or mark a jump, emitted
+/// before the bar's notes (everything except `Repeat::Close`, which closes
+/// a `\repeat volta` block started by an earlier bar).
+fn pre_repeats(bar: &Bar) -> Vec<Repeat> {
+    bar.repeat.iter().filter_map(|s| s.parse().ok()).filter(|r| !matches!(r, Repeat::Close)).collect()
+}
+
+fn has_close(bar: &Bar) -> bool {
+    bar.repeat.iter().filter_map(|s| s.parse::<Repeat>().ok()).any(|r| matches!(r, Repeat::Close))
+}
+
+fn repeat_prefix(repeat: &Repeat) -> String {
+    match repeat {
+        Repeat::Open => "\\repeat volta 2 {".to_string(),
+        Repeat::Close => String::new(), // Handled separately by `has_close`.
+        Repeat::Segno => "\\mark \\markup { \\musicglyph #\"scripts.segno\" }".to_string(),
+        Repeat::DC => "\\mark \\markup { \"D.C.\" }".to_string(),
+        Repeat::DS => "\\mark \\markup { \"D.S.\" }".to_string(),
+        Repeat::Coda => "\\mark \\markup { \\musicglyph #\"scripts.coda\" }".to_string(),
+        Repeat::ToCoda => "\\mark \\markup { \"To Coda\" }".to_string(),
+        Repeat::Fine => "\\mark \\markup { \"Fine\" }".to_string(),
+        // Lacking a lookahead pass to group endings into one `\alternative`
+        // block, a numbered ending is rendered as a plain rehearsal-style
+        // mark rather than proper `\volta` nesting.
+        Repeat::Ending(n) => format!("\\mark \\markup {{ \"{}.\" }}", n),
+    }
+}
+
+fn write_sig(out: &mut String, sig: &Sig) {
+    writeln!(out, "      \\time {}", sig.time).unwrap();
+    writeln!(out, "      \\key {} \\major", key_to_lilypond(sig.key)).unwrap();
+    if sig.tempo > 0 {
+        writeln!(out, "      \\tempo 4 = {}", sig.tempo).unwrap();
+    }
+}
+
+/// Nearest 12-tone pitch name for a key signature expressed in quarter
+/// steps above C (odd values fall between two semitones and are rounded
+/// down).
+fn key_to_lilypond(key: u8) -> &'static str {
+    const NAMES: [&str; 12] =
+        ["c", "cis", "d", "dis", "e", "f", "fis", "g", "gis", "a", "ais", "b"];
+    NAMES[usize::from((key / 2) % 12)]
+}
+
+fn marking_to_lilypond(marking: &Marking) -> String {
+    match marking {
+        Marking::Note(note) => note_to_lilypond(note),
+        Marking::GraceInto(note) => format!("\\grace {{ {} }}", note_to_lilypond(note)),
+        Marking::GraceOutOf(note) => format!("\\grace {{ {} }}", note_to_lilypond(note)),
+        Marking::Dynamic(dynamic) => format!("\\{}", dynamic_to_lilypond(dynamic)),
+        Marking::Breath => "\\breathe".to_string(),
+        Marking::CaesuraShort => "\\breathe".to_string(),
+        Marking::CaesuraLong => "\\breathe".to_string(),
+        Marking::Cresc => "\\<".to_string(),
+        Marking::Dim => "\\>".to_string(),
+        Marking::Pizz => "^\\markup { \"pizz.\" }".to_string(),
+        Marking::Arco => "^\\markup { \"arco\" }".to_string(),
+        Marking::Mute => "^\\markup { \"con sord.\" }".to_string(),
+        Marking::Open => "^\\markup { \"senza sord.\" }".to_string(),
+        // Repeat info lives in `Bar::repeat`, handled separately.
+        Marking::Repeat => String::new(),
+    }
+}
+
+fn dynamic_to_lilypond(dynamic: &Dynamic) -> &'static str {
+    use Dynamic::*;
+
+    match dynamic {
+        PPPPPP => "pppppp",
+        PPPPP => "ppppp",
+        PPPP => "pppp",
+        PPP => "ppp",
+        PP => "pp",
+        P => "p",
+        MP => "mp",
+        MF => "mf",
+        F => "f",
+        FF => "ff",
+        FFF => "fff",
+        FFFF => "ffff",
+        FFFFF => "fffff",
+        FFFFFF => "ffffff",
+        N => "n",
+        SF => "sf",
+        SFZ => "sfz",
+        FP => "fp",
+        SFP => "sfp",
+    }
+}
+
+fn note_to_lilypond(note: &Note) -> String {
+    let (duration, tuplet) = lily_duration(note.duration);
+
+    let pitch = match &note.pitch {
+        Some((class, octave)) => {
+            format!("{}{}", pitch_to_lilypond(class.name, class.accidental), octave_marks(*octave as i8))
+        }
+        None => "r".to_string(),
+    };
+
+    match tuplet {
+        Some((num, den)) => format!("\\times {}/{} {{ {}{} }}", num, den, pitch, duration),
+        None => format!("{}{}", pitch, duration),
+    }
+}
+
+fn pitch_to_lilypond(name: PitchName, accidental: Option<PitchAccidental>) -> String {
+    let letter = match name {
+        PitchName::C => "c",
+        PitchName::D => "d",
+        PitchName::E => "e",
+        PitchName::F => "f",
+        PitchName::G => "g",
+        PitchName::A => "a",
+        PitchName::B => "b",
+    };
+
+    let accidental = match accidental {
+        None | Some(PitchAccidental::Natural) => "",
+        Some(PitchAccidental::DoubleFlat) => "eses",
+        Some(PitchAccidental::FlatQuarterFlat) => "eseh",
+        Some(PitchAccidental::Flat) => "es",
+        Some(PitchAccidental::QuarterFlat) => "eh",
+        Some(PitchAccidental::QuarterSharp) => "ih",
+        Some(PitchAccidental::Sharp) => "is",
+        Some(PitchAccidental::SharpQuarterSharp) => "isih",
+        Some(PitchAccidental::DoubleSharp) => "isis",
+    };
+
+    format!("{}{}", letter, accidental)
+}
+
+/// LilyPond octave marks (`'` raises, `,` lowers) relative to the
+/// unmarked octave, which is `Octave3`.
+fn octave_marks(octave: i8) -> String {
+    let diff = octave - 3;
+    if diff >= 0 {
+        "'".repeat(diff as usize)
+    } else {
+        ",".repeat((-diff) as usize)
+    }
+}
+
+/// LilyPond duration token (e.g. `"4."` for a dotted quarter) for a
+/// fraction of a whole note, plus a tuplet ratio to wrap it in
+/// `\times num/den { }` when the fraction isn't a plain dotted duration.
+fn lily_duration(duration: Fraction) -> (String, Option<(u32, u32)>) {
+    let duration = duration.reduce();
+    let (num, den) = (u32::from(duration.num), u32::from(duration.den));
+
+    if den.is_power_of_two() {
+        for dots in 0..=4u32 {
+            if num == (1 << (dots + 1)) - 1 {
+                let base_den = den >> dots;
+                if base_den >= 1 {
+                    return (format!("{}{}", base_den, ".".repeat(dots as usize)), None);
+                }
+            }
+        }
+    }
+
+    let mut printed_den = 1u32;
+    while printed_den < den {
+        printed_den *= 2;
+    }
+
+    let ratio_num = num * printed_den;
+    let ratio_den = den;
+    let gcd = gcd_u32(ratio_num, ratio_den);
+
+    (format!("{}", printed_den), Some((ratio_num / gcd, ratio_den / gcd)))
+}
+
+fn gcd_u32(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a.max(1)
+}
+
+/// Notes for a channel (shared accessor, since `Chan::notes` is private).
+fn notes(chan: &Chan) -> &[String] {
+    &chan.notes
+}
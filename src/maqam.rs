@@ -0,0 +1,102 @@
+//! Key-signature alterations for `Sig::key`, including 24-EDO maqam/makam
+//! key signatures (`Sig::key` 24+).
+//!
+//! `Note::frequency`/`PitchClass::semitone_offset` already resolve
+//! quarter-tone accidentals to the right fractional MIDI number (and so the
+//! right `2^(1/24)`-per-quarter-tone frequency ratio), so tuning a note to
+//! one of these key signatures is just a matter of picking the right
+//! [`PitchAccidental`] for its letter name via [`effective_accidental`].
+
+use crate::{PitchAccidental, PitchName};
+
+/// A key-signature alteration.  Narrower than `PitchAccidental`: key
+/// signatures only ever raise or lower a letter name by a semitone or a
+/// quarter tone, never a whole tone.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Alteration {
+    Flat,
+    SemiFlat,
+    Sharp,
+    SemiSharp,
+}
+
+impl Alteration {
+    fn accidental(self) -> PitchAccidental {
+        match self {
+            Alteration::Flat => PitchAccidental::Flat,
+            Alteration::SemiFlat => PitchAccidental::QuarterFlat,
+            Alteration::Sharp => PitchAccidental::Sharp,
+            Alteration::SemiSharp => PitchAccidental::QuarterSharp,
+        }
+    }
+}
+
+/// Order letters are added in as a standard major key signature picks up
+/// sharps (`F C G D A E B`) or flats (`B E A D G C F`).
+const ORDER_OF_SHARPS: [PitchName; 7] =
+    [PitchName::F, PitchName::C, PitchName::G, PitchName::D, PitchName::A, PitchName::E, PitchName::B];
+const ORDER_OF_FLATS: [PitchName; 7] =
+    [PitchName::B, PitchName::E, PitchName::A, PitchName::D, PitchName::G, PitchName::C, PitchName::F];
+
+/// Sharps (positive) or flats (negative) in the standard major key
+/// signature for each of the 12 semitone tonics, indexed `0` (C) to `11`
+/// (B).
+const SHARPS_BY_SEMITONE: [i8; 12] = [0, -5, 2, -3, 4, -1, 6, 1, -4, 3, -2, 5];
+
+/// Key signature for a standard (semitone-tonic) key: `key / 2` gives the
+/// tonic's semitone above C by the doc comment on `Sig::key`.  Odd `key`
+/// values (a quarter-tone tonic) currently reuse the signature of the
+/// preceding even key, pending dedicated microtonal circle-of-fifths
+/// support.
+fn standard_key_signature(key: u8) -> Vec<(PitchName, Alteration)> {
+    let semitone = usize::from((key / 2) % 12);
+    let sharps = SHARPS_BY_SEMITONE[semitone];
+
+    if sharps >= 0 {
+        ORDER_OF_SHARPS.iter().take(sharps as usize).map(|&name| (name, Alteration::Sharp)).collect()
+    } else {
+        ORDER_OF_FLATS.iter().take((-sharps) as usize).map(|&name| (name, Alteration::Flat)).collect()
+    }
+}
+
+/// Maqam/makam key signatures, analogous to LilyPond's maqam support: each
+/// entry lists the letters altered by a quarter tone or a semitone
+/// relative to natural, for a representative
+/// realization of the maqam.  This is a representative sample, not an
+/// exhaustive maqam table.
+fn maqam_key_signature(key: u8) -> Vec<(PitchName, Alteration)> {
+    match key {
+        // Rast (on C): C D E-half-flat F G A B-half-flat C.
+        24 => vec![(PitchName::E, Alteration::SemiFlat), (PitchName::B, Alteration::SemiFlat)],
+        // Bayati (on D): D E-half-flat F G A Bb C D.
+        25 => vec![(PitchName::E, Alteration::SemiFlat), (PitchName::B, Alteration::Flat)],
+        // Hijaz (on D): D Eb F# G A Bb C D.
+        26 => vec![(PitchName::E, Alteration::Flat), (PitchName::F, Alteration::Sharp), (PitchName::B, Alteration::Flat)],
+        // Saba (on D): D E-half-flat F Gb A Bb C D.
+        27 => vec![(PitchName::E, Alteration::SemiFlat), (PitchName::G, Alteration::Flat), (PitchName::B, Alteration::Flat)],
+        // Kurd (on D): D Eb F G A Bb C D.
+        28 => vec![(PitchName::E, Alteration::Flat), (PitchName::B, Alteration::Flat)],
+        _ => vec![],
+    }
+}
+
+/// Ordered `(pitch-step, alteration)` pairs for `key`, per the doc comment
+/// on `Sig::key`: `0..=23` are standard key signatures addressed by tonic
+/// (`key / 2` semitones above C), `24+` are maqam/makam key signatures.
+pub fn key_signature(key: u8) -> Vec<(PitchName, Alteration)> {
+    if key < 24 {
+        standard_key_signature(key)
+    } else {
+        maqam_key_signature(key)
+    }
+}
+
+/// The accidental that `name` should sound as under `key`'s key signature,
+/// or `None` if the key signature doesn't alter it (naturals still apply,
+/// as in standard notation).
+pub fn effective_accidental(key: u8, name: PitchName) -> Option<PitchAccidental> {
+    key_signature(key)
+        .into_iter()
+        .find(|(step, _)| step.step() == name.step())
+        .map(|(_, alteration)| alteration.accidental())
+}
@@ -0,0 +1,176 @@
+//! Standard MIDI File (Type 1) export of a [`Scof`].
+//!
+//! Track 0 is the conductor track (tempo and time signature meta events);
+//! one track per channel follows, built from the first movement's bars.
+//! Each [`Chan`] holds a single melodic line (no overlapping notes), so a
+//! note's `NoteOff` always falls at or before the next note's `NoteOn` —
+//! there's no need to interleave across notes.  The note grammar has no
+//! tie marker (see `note/mod.rs`), so a tied note is already just a single
+//! `Note` with a longer combined duration; nothing needs merging here.
+
+use crate::{Fraction, Marking, Movement, Scof};
+
+/// Ticks per quarter note.
+const DIVISION: u16 = 480;
+
+const DEFAULT_VELOCITY: u8 = 80;
+
+pub fn to_midi(scof: &Scof) -> Vec<u8> {
+    let movement = match scof.movement.get(0) {
+        Some(movement) => movement,
+        None => return vec![],
+    };
+
+    let num_chans = movement.bar.get(0).map(|bar| bar.chan.len()).unwrap_or(0);
+
+    let mut tracks = vec![conductor_track(movement)];
+    for c in 0..num_chans {
+        tracks.push(channel_track(movement, c));
+    }
+
+    let mut out = Vec::new();
+    write_header(&mut out, tracks.len() as u16);
+    for track in &tracks {
+        write_track_chunk(&mut out, track);
+    }
+    out
+}
+
+fn write_header(out: &mut Vec<u8>, ntrks: u16) {
+    out.extend_from_slice(b"MThd");
+    out.extend_from_slice(&6u32.to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes()); // Format 1: simultaneous tracks.
+    out.extend_from_slice(&ntrks.to_be_bytes());
+    out.extend_from_slice(&DIVISION.to_be_bytes());
+}
+
+fn write_track_chunk(out: &mut Vec<u8>, track: &[u8]) {
+    out.extend_from_slice(b"MTrk");
+    out.extend_from_slice(&(track.len() as u32).to_be_bytes());
+    out.extend_from_slice(track);
+}
+
+/// Encode `value` as a MIDI variable-length quantity.
+fn write_vlq(out: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut value = value >> 7;
+    while value > 0 {
+        groups.push(((value & 0x7F) as u8) | 0x80);
+        value >>= 7;
+    }
+    while let Some(group) = groups.pop() {
+        out.push(group);
+    }
+}
+
+/// Conductor track: a `Set Tempo` and `Time Signature` meta event each
+/// time the movement's signature changes, ending with `End of Track`.
+fn conductor_track(movement: &Movement) -> Vec<u8> {
+    let mut track = Vec::new();
+    let mut current_sig = None;
+
+    for bar in &movement.bar {
+        let sig_index = match bar.sig {
+            Some(sig_index) => sig_index as usize,
+            None => continue,
+        };
+        if current_sig == Some(sig_index) {
+            continue;
+        }
+        current_sig = Some(sig_index);
+
+        if let Some(sig) = movement.sig.get(sig_index) {
+            write_vlq(&mut track, 0);
+            write_tempo_event(&mut track, sig.tempo);
+            write_vlq(&mut track, 0);
+            write_time_signature_event(&mut track, &sig.time);
+        }
+    }
+
+    write_vlq(&mut track, 0);
+    track.extend_from_slice(&[0xFF, 0x2F, 0x00]); // End of Track.
+    track
+}
+
+fn write_tempo_event(track: &mut Vec<u8>, bpm: u16) {
+    let bpm = u32::from(if bpm == 0 { 120 } else { bpm });
+    let micros_per_quarter = (60_000_000 / bpm).to_be_bytes();
+    track.extend_from_slice(&[0xFF, 0x51, 0x03, micros_per_quarter[1], micros_per_quarter[2], micros_per_quarter[3]]);
+}
+
+fn write_time_signature_event(track: &mut Vec<u8>, time: &str) {
+    let mut parts = time.splitn(2, '/');
+    let numerator: u8 = parts.next().and_then(|n| n.parse().ok()).unwrap_or(4);
+    let denominator: u8 = parts.next().and_then(|n| n.parse().ok()).unwrap_or(4);
+    let denominator_power = (f32::from(denominator).log2().round() as u8).max(0);
+
+    // 24 MIDI clocks per metronome click, 8 32nd-notes per quarter note.
+    track.extend_from_slice(&[0xFF, 0x58, 0x04, numerator, denominator_power, 24, 8]);
+}
+
+/// One track of NoteOn/NoteOff events for channel `c`.
+fn channel_track(movement: &Movement, c: usize) -> Vec<u8> {
+    let mut track = Vec::new();
+    let midi_chan = (c % 16) as u8;
+
+    let mut current_tick: u32 = 0;
+    let mut last_event_tick: u32 = 0;
+    let mut velocity = DEFAULT_VELOCITY;
+
+    for bar in &movement.bar {
+        let chan = match bar.chan.get(c) {
+            Some(chan) => chan,
+            None => continue,
+        };
+
+        for note_str in &chan.notes {
+            let marking: Marking = match note_str.parse() {
+                Ok(marking) => marking,
+                Err(_) => continue,
+            };
+
+            let note = match marking {
+                Marking::Dynamic(dynamic) => {
+                    velocity = dynamic.velocity();
+                    continue;
+                }
+                Marking::Note(note) => note,
+                _ => continue, // No MIDI equivalent for the other markings.
+            };
+
+            let note_ticks = duration_ticks(note.duration);
+
+            let midi_number = match note.midi_number(true) {
+                Some(midi_number) => midi_number,
+                None => {
+                    current_tick += note_ticks; // Rest.
+                    continue;
+                }
+            };
+
+            write_vlq(&mut track, current_tick - last_event_tick);
+            track.push(0x90 | midi_chan);
+            track.push(midi_number);
+            track.push(velocity);
+            last_event_tick = current_tick;
+
+            let note_off_tick = current_tick + note_ticks;
+            write_vlq(&mut track, note_off_tick - last_event_tick);
+            track.push(0x80 | midi_chan);
+            track.push(midi_number);
+            track.push(0);
+            last_event_tick = note_off_tick;
+
+            current_tick = note_off_tick;
+        }
+    }
+
+    write_vlq(&mut track, 0);
+    track.extend_from_slice(&[0xFF, 0x2F, 0x00]); // End of Track.
+    track
+}
+
+/// Ticks for a duration expressed as a fraction of a whole note.
+fn duration_ticks(duration: Fraction) -> u32 {
+    u32::from(duration.num) * 4 * u32::from(DIVISION) / u32::from(duration.den)
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 /// An articulation (affects how the note is played).
 #[derive(Copy, Clone)]
 pub enum Articulation {
@@ -46,3 +48,43 @@ pub enum Articulation {
     /// Pedal
     Pedal,
 }
+
+impl Articulation {
+    /// Parse the single-character articulation token at the start of `s`
+    /// (one of `^ > . ' _`), returning the articulation and the number of
+    /// bytes it consumed.  The two-character combos in the note grammar
+    /// (`_.`, `^.`, `^_`, `>.`, `>_`) fall out of parsing each character in
+    /// turn, so there's no separate combo handling here.
+    pub fn parse_prefix(s: &str) -> Option<(Articulation, usize)> {
+        let c = s.chars().next()?;
+
+        let articulation = match c {
+            '^' => Articulation::Marcato,
+            '>' => Articulation::Accent,
+            '.' => Articulation::Staccato,
+            '\'' => Articulation::Staccatissimo,
+            '_' => Articulation::Tenuto,
+            _ => return None,
+        };
+
+        Some((articulation, c.len_utf8()))
+    }
+}
+
+impl fmt::Display for Articulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Articulation::*;
+
+        match self {
+            Marcato => write!(f, "^"),
+            Accent => write!(f, ">"),
+            Staccato => write!(f, "."),
+            Staccatissimo => write!(f, "'"),
+            Tenuto => write!(f, "_"),
+            // The remaining articulations have no single-character token in
+            // the note grammar documented in `note/mod.rs`; they're never
+            // produced by `parse_prefix` and have nothing to write here.
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! # Interval
+//! An interval describes the distance between two pitches as a signed
+//! semitone count, optionally paired with a diatonic scale-degree count
+//! used to pick the correct spelling of the destination pitch.
+
+use super::{Note, PitchAccidental, PitchClass, PitchName, PitchOctave};
+
+/// A musical interval.
+#[derive(Copy, Clone)]
+pub struct Interval {
+    /// Size of the interval in semitones (negative descends).
+    pub semitones: i8,
+    /// Diatonic scale-degree count (e.g. a third is 2 steps), when known.
+    /// Used to bias re-spelling so an augmented fourth stays F→B rather
+    /// than F→C♭.
+    pub diatonic_steps: Option<i8>,
+}
+
+impl Interval {
+    /// Create a new interval from a semitone count and optional diatonic
+    /// scale-degree count.
+    pub fn new(semitones: i8, diatonic_steps: Option<i8>) -> Self {
+        Interval { semitones, diatonic_steps }
+    }
+
+    /// Minor second (1 semitone, 1 diatonic step).
+    pub fn minor_second() -> Self {
+        Interval::new(1, Some(1))
+    }
+
+    /// Major second (2 semitones, 1 diatonic step).
+    pub fn major_second() -> Self {
+        Interval::new(2, Some(1))
+    }
+
+    /// Minor third (3 semitones, 2 diatonic steps).
+    pub fn minor_third() -> Self {
+        Interval::new(3, Some(2))
+    }
+
+    /// Major third (4 semitones, 2 diatonic steps).
+    pub fn major_third() -> Self {
+        Interval::new(4, Some(2))
+    }
+
+    /// Perfect fourth (5 semitones, 3 diatonic steps).
+    pub fn perfect_fourth() -> Self {
+        Interval::new(5, Some(3))
+    }
+
+    /// Augmented fourth / tritone (6 semitones, 3 diatonic steps).
+    pub fn augmented_fourth() -> Self {
+        Interval::new(6, Some(3))
+    }
+
+    /// Perfect fifth (7 semitones, 4 diatonic steps).
+    pub fn perfect_fifth() -> Self {
+        Interval::new(7, Some(4))
+    }
+
+    /// Minor sixth (8 semitones, 5 diatonic steps).
+    pub fn minor_sixth() -> Self {
+        Interval::new(8, Some(5))
+    }
+
+    /// Major sixth (9 semitones, 5 diatonic steps).
+    pub fn major_sixth() -> Self {
+        Interval::new(9, Some(5))
+    }
+
+    /// Minor seventh (10 semitones, 6 diatonic steps).
+    pub fn minor_seventh() -> Self {
+        Interval::new(10, Some(6))
+    }
+
+    /// Major seventh (11 semitones, 6 diatonic steps).
+    pub fn major_seventh() -> Self {
+        Interval::new(11, Some(6))
+    }
+
+    /// Perfect octave (12 semitones, 7 diatonic steps).
+    pub fn octave() -> Self {
+        Interval::new(12, Some(7))
+    }
+
+    /// Semitone distance from `a` to `b`, rounding away any quarter-tone
+    /// accidentals.  Returns `None` if either note is a rest.
+    pub fn between(a: &Note, b: &Note) -> Option<i8> {
+        let (class_a, octave_a) = a.pitch.as_ref()?;
+        let (class_b, octave_b) = b.pitch.as_ref()?;
+
+        let midi_a = class_a.midi_number_exact(*octave_a);
+        let midi_b = class_b.midi_number_exact(*octave_b);
+
+        Some((midi_b - midi_a).round() as i8)
+    }
+
+    /// Apply this interval to a pitch, re-spelling the result.  When
+    /// `diatonic_steps` is known, the destination letter name is chosen by
+    /// that many diatonic steps rather than by nearest-below semitone, so
+    /// e.g. an augmented fourth from F lands on B rather than C♭.
+    pub fn apply(&self, pitch: (PitchClass, PitchOctave)) -> Option<(PitchClass, PitchOctave)> {
+        let (class, octave) = pitch;
+
+        let diatonic_steps = match self.diatonic_steps {
+            Some(steps) => steps,
+            None => return class.transpose_semitones(octave, self.semitones as f32),
+        };
+
+        let total = class.semitone_offset().round() as i32
+            + i32::from(octave as i8) * 12
+            + i32::from(self.semitones);
+        let mut new_octave = total.div_euclid(12);
+        let pitch_class_in_octave = total.rem_euclid(12);
+
+        let new_name = PitchName::from_step(class.name.step() + diatonic_steps);
+        let natural = i32::from(new_name.semitone());
+
+        let mut diff = pitch_class_in_octave - natural;
+        if diff > 6 {
+            diff -= 12;
+            new_octave += 1;
+        } else if diff < -6 {
+            diff += 12;
+            new_octave -= 1;
+        }
+
+        let accidental = match diff {
+            -2 => Some(PitchAccidental::DoubleFlat),
+            -1 => Some(PitchAccidental::Flat),
+            0 => None,
+            1 => Some(PitchAccidental::Sharp),
+            2 => Some(PitchAccidental::DoubleSharp),
+            _ => return None,
+        };
+
+        let new_octave = PitchOctave::from_i8(new_octave as i8)?;
+
+        Some((PitchClass { name: new_name, accidental }, new_octave))
+    }
+}
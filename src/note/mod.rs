@@ -61,10 +61,12 @@ use crate::Fraction;
 mod articulation;
 mod pitch;
 mod duration;
+mod interval;
 
 pub use self::articulation::*;
 pub use self::pitch::*;
 pub use self::duration::*;
+pub use self::interval::*;
 
 /// A note.
 pub struct Note {
@@ -84,7 +86,7 @@ impl fmt::Display for Note {
         }
         write!(f, "{}", self.duration.den)?;
 
-        // Write note name & octave.
+        // Write note name, accidental & octave.
         match &self.pitch {
             Some(pitch) => {
                 let class = match pitch.0.name {
@@ -96,11 +98,37 @@ impl fmt::Display for Note {
                     PitchName::F => "F",
                     PitchName::G => "G",
                 };
-                write!(f, "{}{}", class, pitch.1)
+                write!(f, "{}", class)?;
+                if let Some(accidental) = pitch.0.accidental {
+                    write!(f, "{}", accidental)?;
+                }
+                write!(f, "{}", pitch.1)?;
             },
-            None => write!(f, "R"),
+            None => write!(f, "R")?,
+        }
+
+        // Write articulation.
+        for articulation in &self.articulation {
+            write!(f, "{}", articulation)?;
         }
+
+        Ok(())
+    }
+}
+
+/// Parse the trailing articulation tokens (`^ > . ' _` and their combos) at
+/// the start of `s`.
+fn parse_articulations(s: &str) -> Result<Vec<Articulation>, ()> {
+    let mut articulation = vec![];
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let (art, len) = Articulation::parse_prefix(rest).ok_or(())?;
+        articulation.push(art);
+        rest = &rest[len..];
     }
+
+    Ok(articulation)
 }
 
 impl FromStr for Note {
@@ -141,74 +169,59 @@ impl FromStr for Note {
 
             Fraction::new(numer, denom)
         };
-        let articulation = vec![];
 
         // Read note name.
-        match s.get(end_index..).ok_or(())? {
-            "R" => Ok(Note {
+        let a = s.get(end_index..).ok_or(())?;
+        if let Some(rest) = a.strip_prefix('R') {
+            return Ok(Note {
                 pitch: None,
                 duration,
-                articulation,
-            }),
-            a => {
-                let two = a.chars().collect::<Vec<char>>();
-                let letter_name = two[0];
-                let octave_num = match two[1] {
-                    '-' => PitchOctave::Octave_,
-                    '0' => PitchOctave::Octave0,
-                    '1' => PitchOctave::Octave1,
-                    '2' => PitchOctave::Octave2,
-                    '3' => PitchOctave::Octave3,
-                    '4' => PitchOctave::Octave4,
-                    '5' => PitchOctave::Octave5,
-                    '6' => PitchOctave::Octave6,
-                    '7' => PitchOctave::Octave7,
-                    '8' => PitchOctave::Octave8,
-                    '9' => PitchOctave::Octave9,
-                    _ => return Err(()),
-                };
-
-                Ok(Note {
-                    pitch: Some((
-                        match letter_name {
-                            'A' => PitchClass {
-                                name: PitchName::A,
-                                accidental: None,
-                            },
-                            'B' => PitchClass {
-                                name: PitchName::B,
-                                accidental: None,
-                            },
-                            'C' => PitchClass {
-                                name: PitchName::C,
-                                accidental: None,
-                            },
-                            'D' => PitchClass {
-                                name: PitchName::D,
-                                accidental: None,
-                            },
-                            'E' => PitchClass {
-                                name: PitchName::E,
-                                accidental: None,
-                            },
-                            'F' => PitchClass {
-                                name: PitchName::F,
-                                accidental: None,
-                            },
-                            'G' => PitchClass {
-                                name: PitchName::G,
-                                accidental: None,
-                            },
-                            // FIXME: return Err
-                            a => panic!("Failed to parse '{}'", a),
-                        },
-                        octave_num,
-                    )),
-                    duration,
-                    articulation,
-                })
-            }
+                articulation: parse_articulations(rest)?,
+            });
         }
+
+        let mut chars = a.chars();
+        let letter_name = chars.next().ok_or(())?;
+        let name = match letter_name {
+            'A' => PitchName::A,
+            'B' => PitchName::B,
+            'C' => PitchName::C,
+            'D' => PitchName::D,
+            'E' => PitchName::E,
+            'F' => PitchName::F,
+            'G' => PitchName::G,
+            _ => return Err(()),
+        };
+        let rest = chars.as_str();
+
+        let (accidental, consumed) = PitchAccidental::parse_prefix(rest);
+        let rest = &rest[consumed..];
+
+        let mut chars = rest.chars();
+        let octave_num = match chars.next().ok_or(())? {
+            '-' => PitchOctave::Octave_,
+            '0' => PitchOctave::Octave0,
+            '1' => PitchOctave::Octave1,
+            '2' => PitchOctave::Octave2,
+            '3' => PitchOctave::Octave3,
+            '4' => PitchOctave::Octave4,
+            '5' => PitchOctave::Octave5,
+            '6' => PitchOctave::Octave6,
+            '7' => PitchOctave::Octave7,
+            '8' => PitchOctave::Octave8,
+            '9' => PitchOctave::Octave9,
+            _ => return Err(()),
+        };
+        let rest = chars.as_str();
+
+        Ok(Note {
+            pitch: Some((
+                PitchClass { name, accidental },
+                octave_num,
+            )),
+            duration,
+            articulation: parse_articulations(rest)?,
+        })
     }
 }
 
@@ -248,6 +261,62 @@ impl Note {
         self.duration = duration;
     }
 
+    /// Convert this note's pitch to a MIDI note number (C4 = 60, A4 = 69).
+    ///
+    /// Returns `None` for rests, and for pitches with a quarter-tone
+    /// accidental unless `round` is set.
+    pub fn midi_number(&self, round: bool) -> Option<u8> {
+        let (class, octave) = self.pitch.as_ref()?;
+        class.to_midi(*octave, round)
+    }
+
+    /// Frequency of this note's pitch in Hz, given the frequency of concert
+    /// pitch A4.  Returns `None` for rests.  Honors quarter-tone accidentals.
+    pub fn frequency(&self, concert_a: f64) -> Option<f64> {
+        let (class, octave) = self.pitch.as_ref()?;
+        let midi = class.midi_number_exact(*octave);
+
+        Some(concert_a * 2f64.powf((midi - 69.0) / 12.0))
+    }
+
+    /// Frequency of this note's pitch in Hz, assuming A4 = 440 Hz.
+    pub fn frequency_440(&self) -> Option<f64> {
+        self.frequency(440.0)
+    }
+
+    /// Interval between this note and `other`, in cents.  Independent of
+    /// concert pitch.  Returns `0.0` if either note is a rest.
+    pub fn cents_from(&self, other: &Note) -> f64 {
+        match (self.frequency_440(), other.frequency_440()) {
+            (Some(f1), Some(f2)) => 1200.0 * (f1 / f2).log2(),
+            _ => 0.0,
+        }
+    }
+
+    /// Transpose this note's pitch by `interval`, re-spelling the result.
+    /// Rests and pitches that would overflow past `Octave9`/`Octave_` are
+    /// returned unchanged.
+    pub fn transpose(&self, interval: Interval) -> Note {
+        let pitch = self.pitch.map(|pitch| interval.apply(pitch).unwrap_or(pitch));
+
+        Note {
+            pitch,
+            duration: self.duration,
+            articulation: self.articulation.clone(),
+        }
+    }
+
+    /// Build a note from a MIDI note number, picking a default spelling
+    /// (naturals first, then sharps), with the given `duration` and no
+    /// articulation.
+    pub fn from_midi_number(n: u8, duration: Fraction) -> Note {
+        Note {
+            pitch: Some(PitchClass::from_midi(n)),
+            duration,
+            articulation: vec![],
+        }
+    }
+
     fn move_step(&self, create: (PitchClass, PitchOctave), run: &dyn Fn(&(PitchClass, PitchOctave)) -> Option<(PitchClass, PitchOctave)>) -> Note {
         let pitch = if let Some(ref pitch) = self.pitch {
             (run)(pitch)
@@ -262,24 +331,33 @@ impl Note {
         }
     }
 
+    /// Move to the chromatic pitch `delta` semitones away, re-spelling the
+    /// result.  Falls back to the unchanged pitch if the octave would
+    /// overflow past `Octave9`/`Octave_`.
+    fn move_chromatic(&self, create: (PitchClass, PitchOctave), delta: f32) -> Note {
+        self.move_step(create, &|pitch| {
+            Some(pitch.0.transpose_semitones(pitch.1, delta).unwrap_or((pitch.0, pitch.1)))
+        })
+    }
+
     /// Calculate note one quarter step up.
     pub fn quarter_step_up(&self, create: (PitchClass, PitchOctave)) -> Note {
-        self.step_up(create) // FIXME
+        self.move_chromatic(create, 0.5)
     }
 
     /// Calculate note one quarter step down.
     pub fn quarter_step_down(&self, create: (PitchClass, PitchOctave)) -> Note {
-        self.step_down(create) // FIXME
+        self.move_chromatic(create, -0.5)
     }
 
     /// Calculate note one half step up.
     pub fn half_step_up(&self, create: (PitchClass, PitchOctave)) -> Note {
-        self.step_up(create) // FIXME
+        self.move_chromatic(create, 1.0)
     }
 
     /// Calculate note one half step down.
     pub fn half_step_down(&self, create: (PitchClass, PitchOctave)) -> Note {
-        self.step_down(create) // FIXME
+        self.move_chromatic(create, -1.0)
     }
 
     /// Calculate note one step up within the key.
@@ -342,3 +420,34 @@ impl Note {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_accidentals_and_articulations() {
+        let notes = [
+            "4Abb4^.",
+            "8G#3>_",
+            "16Dt4'",
+            "4Fdb2",
+            "4Bt#5",
+            "2Dn4",
+            "8C4^",
+            "4R.",
+            "4C4",
+        ];
+
+        for s in notes.iter() {
+            let note: Note = s.parse().unwrap();
+            assert_eq!(&note.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn invalid_note_is_err() {
+        assert!("4H4".parse::<Note>().is_err());
+        assert!("4Az4".parse::<Note>().is_err());
+    }
+}
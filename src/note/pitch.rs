@@ -1,5 +1,13 @@
+use std::convert::TryFrom;
 use std::fmt;
 
+/// Bitfield value for "no accidental specified" in [`PackedPitch`].  Shares
+/// `Natural`'s index (4) since an unspecified accidental has the same
+/// pitch height as an explicit natural; unpacking this index always
+/// produces `None` rather than `Some(Natural)`, so packing an explicit
+/// `Natural` accidental is lossy — it comes back as `None`.
+const NO_ACCIDENTAL_INDEX: u8 = 4;
+
 /// A Pitch Name.
 #[derive(Copy, Clone)]
 pub enum PitchName {
@@ -35,6 +43,152 @@ pub enum PitchAccidental {
     DoubleSharp,
 }
 
+impl PitchName {
+    /// Pitch class semitone value within the octave (C=0, D=2, E=4, F=5, G=7,
+    /// A=9, B=11).
+    pub fn semitone(&self) -> i8 {
+        match self {
+            PitchName::C => 0,
+            PitchName::D => 2,
+            PitchName::E => 4,
+            PitchName::F => 5,
+            PitchName::G => 7,
+            PitchName::A => 9,
+            PitchName::B => 11,
+        }
+    }
+
+    /// Diatonic scale-degree index (C=0, D=1, E=2, F=3, G=4, A=5, B=6).
+    pub fn step(&self) -> i8 {
+        match self {
+            PitchName::C => 0,
+            PitchName::D => 1,
+            PitchName::E => 2,
+            PitchName::F => 3,
+            PitchName::G => 4,
+            PitchName::A => 5,
+            PitchName::B => 6,
+        }
+    }
+
+    /// Build a `PitchName` from a diatonic scale-degree index, wrapping
+    /// modulo 7.
+    pub fn from_step(step: i8) -> PitchName {
+        match step.rem_euclid(7) {
+            0 => PitchName::C,
+            1 => PitchName::D,
+            2 => PitchName::E,
+            3 => PitchName::F,
+            4 => PitchName::G,
+            5 => PitchName::A,
+            _ => PitchName::B,
+        }
+    }
+}
+
+impl PitchAccidental {
+    /// Offset from the natural pitch, in semitones.  Quarter-tone
+    /// accidentals yield a half-integer offset.
+    pub fn semitone_offset(&self) -> f32 {
+        use PitchAccidental::*;
+
+        match self {
+            DoubleFlat => -2.0,
+            FlatQuarterFlat => -1.5,
+            Flat => -1.0,
+            QuarterFlat => -0.5,
+            Natural => 0.0,
+            QuarterSharp => 0.5,
+            Sharp => 1.0,
+            SharpQuarterSharp => 1.5,
+            DoubleSharp => 2.0,
+        }
+    }
+
+    /// Bitfield index (0-8), ordered the same as `semitone_offset` so that
+    /// comparing indices compares pitch height.
+    pub fn index(&self) -> u8 {
+        use PitchAccidental::*;
+
+        match self {
+            DoubleFlat => 0,
+            FlatQuarterFlat => 1,
+            Flat => 2,
+            QuarterFlat => 3,
+            Natural => 4,
+            QuarterSharp => 5,
+            Sharp => 6,
+            SharpQuarterSharp => 7,
+            DoubleSharp => 8,
+        }
+    }
+
+    /// Inverse of [`index`](Self::index).
+    pub fn from_index(index: u8) -> Option<PitchAccidental> {
+        use PitchAccidental::*;
+
+        Some(match index {
+            0 => DoubleFlat,
+            1 => FlatQuarterFlat,
+            2 => Flat,
+            3 => QuarterFlat,
+            4 => Natural,
+            5 => QuarterSharp,
+            6 => Sharp,
+            7 => SharpQuarterSharp,
+            8 => DoubleSharp,
+            _ => return None,
+        })
+    }
+
+    /// Parse the accidental token (if any) at the start of `s` (one of `bb
+    /// db b d n t # t# x`, see the grammar in `note/mod.rs`), returning the
+    /// accidental and the number of bytes it consumed.
+    pub fn parse_prefix(s: &str) -> (Option<PitchAccidental>, usize) {
+        use PitchAccidental::*;
+
+        // Two-character tokens must be tried before their single-character
+        // prefixes ("bb" before "b", "db" before "d", "t#" before "t").
+        const TOKENS: [(&str, PitchAccidental); 9] = [
+            ("bb", DoubleFlat),
+            ("db", FlatQuarterFlat),
+            ("t#", SharpQuarterSharp),
+            ("b", Flat),
+            ("d", QuarterFlat),
+            ("n", Natural),
+            ("t", QuarterSharp),
+            ("#", Sharp),
+            ("x", DoubleSharp),
+        ];
+
+        for (token, accidental) in TOKENS.iter() {
+            if s.starts_with(token) {
+                return (Some(*accidental), token.len());
+            }
+        }
+
+        (None, 0)
+    }
+}
+
+impl fmt::Display for PitchAccidental {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PitchAccidental::*;
+
+        write!(f, "{}", match self {
+            DoubleFlat => "bb",
+            FlatQuarterFlat => "db",
+            Flat => "b",
+            QuarterFlat => "d",
+            Natural => "n",
+            QuarterSharp => "t",
+            Sharp => "#",
+            SharpQuarterSharp => "t#",
+            DoubleSharp => "x",
+        })
+    }
+}
+
 /// A Pitch Class
 #[derive(Copy, Clone)]
 pub struct PitchClass {
@@ -42,6 +196,145 @@ pub struct PitchClass {
     pub accidental: Option<PitchAccidental>,
 }
 
+impl PitchClass {
+    /// Semitone offset from C, including the accidental.  May be a
+    /// half-integer for quarter-tone accidentals.
+    pub fn semitone_offset(&self) -> f32 {
+        f32::from(self.name.semitone())
+            + self.accidental.map(|a| a.semitone_offset()).unwrap_or(0.0)
+    }
+
+    /// Convert to a MIDI note number (C4 = 60, A4 = 69).
+    ///
+    /// Quarter-tone accidentals put the pitch between two MIDI numbers; pass
+    /// `round` to snap to the nearest one, or leave it `false` to get `None`
+    /// in that case.
+    pub fn to_midi(&self, octave: PitchOctave, round: bool) -> Option<u8> {
+        let offset = self.semitone_offset();
+        let offset = if round {
+            offset.round()
+        } else if offset.fract() != 0.0 {
+            return None;
+        } else {
+            offset
+        };
+
+        let value = (octave as i16 + 1) * 12 + offset as i16;
+
+        u8::try_from(value).ok()
+    }
+
+    /// Exact (possibly fractional) MIDI note number, including the
+    /// fractional contribution of quarter-tone accidentals.
+    pub fn midi_number_exact(&self, octave: PitchOctave) -> f64 {
+        f64::from(self.semitone_offset()) + f64::from(octave as i8 + 1) * 12.0
+    }
+
+    /// Build a `(PitchClass, PitchOctave)` from a MIDI note number, picking a
+    /// default spelling (naturals first, then sharps).
+    pub fn from_midi(n: u8) -> (PitchClass, PitchOctave) {
+        let n = i16::from(n);
+        let octave = n.div_euclid(12) - 1;
+        let pitch_class = n.rem_euclid(12);
+
+        let (name, accidental) = match pitch_class {
+            0 => (PitchName::C, None),
+            1 => (PitchName::C, Some(PitchAccidental::Sharp)),
+            2 => (PitchName::D, None),
+            3 => (PitchName::D, Some(PitchAccidental::Sharp)),
+            4 => (PitchName::E, None),
+            5 => (PitchName::F, None),
+            6 => (PitchName::F, Some(PitchAccidental::Sharp)),
+            7 => (PitchName::G, None),
+            8 => (PitchName::G, Some(PitchAccidental::Sharp)),
+            9 => (PitchName::A, None),
+            10 => (PitchName::A, Some(PitchAccidental::Sharp)),
+            _ => (PitchName::B, None),
+        };
+
+        // Octave is always in range for a `u8` MIDI number.
+        let octave = PitchOctave::from_i8(octave as i8).unwrap_or(PitchOctave::Octave4);
+
+        (PitchClass { name, accidental }, octave)
+    }
+
+    /// Re-spell a semitone offset measured from C within a single octave
+    /// (`0.0..12.0`, possibly a half-integer) by picking the natural letter
+    /// name nearest below it and expressing the remainder as an accidental.
+    pub fn from_offset_in_octave(offset: f32) -> Option<PitchClass> {
+        const NATURALS: [(PitchName, f32); 7] = [
+            (PitchName::C, 0.0),
+            (PitchName::D, 2.0),
+            (PitchName::E, 4.0),
+            (PitchName::F, 5.0),
+            (PitchName::G, 7.0),
+            (PitchName::A, 9.0),
+            (PitchName::B, 11.0),
+        ];
+
+        let (name, natural) = NATURALS.iter().copied()
+            .filter(|(_, natural)| *natural <= offset)
+            .last()?;
+        let diff = offset - natural;
+        let accidental = if diff == 0.0 {
+            None
+        } else if (diff - 0.5).abs() < f32::EPSILON {
+            Some(PitchAccidental::QuarterSharp)
+        } else if (diff - 1.0).abs() < f32::EPSILON {
+            Some(PitchAccidental::Sharp)
+        } else if (diff - 1.5).abs() < f32::EPSILON {
+            Some(PitchAccidental::SharpQuarterSharp)
+        } else if (diff - 2.0).abs() < f32::EPSILON {
+            Some(PitchAccidental::DoubleSharp)
+        } else {
+            return None;
+        };
+
+        Some(PitchClass { name, accidental })
+    }
+
+    /// Pack the letter name and accidental (not the octave) into the low 7
+    /// bits of a byte: letter in bits 4-6, accidental (or "none") in bits
+    /// 0-3.  See [`PackedPitch`].
+    pub fn to_packed(&self) -> u8 {
+        let letter = self.name.step() as u8;
+        let accidental = self.accidental.map(|a| a.index()).unwrap_or(NO_ACCIDENTAL_INDEX);
+
+        (letter << 4) | accidental
+    }
+
+    /// Inverse of [`to_packed`](Self::to_packed).
+    pub fn from_packed(bits: u8) -> PitchClass {
+        let letter = ((bits >> 4) & 0x7) as i8;
+        let accidental_index = bits & 0xF;
+
+        let accidental = if accidental_index == NO_ACCIDENTAL_INDEX {
+            None
+        } else {
+            PitchAccidental::from_index(accidental_index)
+        };
+
+        PitchClass {
+            name: PitchName::from_step(letter),
+            accidental,
+        }
+    }
+
+    /// Move this pitch (at `octave`) by `delta` semitones (may be a
+    /// half-integer), re-spelling and carrying the octave along.  Returns
+    /// `None` if the resulting octave would overflow past `Octave9`/`Octave_`.
+    pub fn transpose_semitones(&self, octave: PitchOctave, delta: f32) -> Option<(PitchClass, PitchOctave)> {
+        let total = self.semitone_offset() + f32::from(octave as i8) * 12.0 + delta;
+        let octave_num = (total / 12.0).floor();
+        let offset = total - octave_num * 12.0;
+
+        let new_octave = PitchOctave::from_i8(octave_num as i8)?;
+        let new_class = PitchClass::from_offset_in_octave(offset)?;
+
+        Some((new_class, new_octave))
+    }
+}
+
 /// A Pitch Octave
 #[derive(Copy, Clone)]
 #[repr(i8)]
@@ -108,6 +401,37 @@ impl PitchOctave {
             Octave9 => None,
         }
     }
+
+    /// Pack into a 4-bit value (0-10), biased so it never goes negative.
+    /// See [`PackedPitch`].
+    pub fn to_packed(&self) -> u8 {
+        (*self as i8 + 1) as u8
+    }
+
+    /// Inverse of [`to_packed`](Self::to_packed).
+    pub fn from_packed(bits: u8) -> Option<PitchOctave> {
+        PitchOctave::from_i8(bits as i8 - 1)
+    }
+
+    /// Build a `PitchOctave` from its numeric value (-1 through 9).
+    pub fn from_i8(value: i8) -> Option<PitchOctave> {
+        use PitchOctave::*;
+
+        Some(match value {
+            -1 => Octave_,
+            0 => Octave0,
+            1 => Octave1,
+            2 => Octave2,
+            3 => Octave3,
+            4 => Octave4,
+            5 => Octave5,
+            6 => Octave6,
+            7 => Octave7,
+            8 => Octave8,
+            9 => Octave9,
+            _ => return None,
+        })
+    }
 }
 
 impl fmt::Display for PitchOctave {
@@ -129,3 +453,135 @@ impl fmt::Display for PitchOctave {
         }
     }
 }
+
+/// Number of bits `PackedPitch` reserves for the packed `PitchClass` (letter
+/// + accidental), so the octave can be shifted above them.
+const PACKED_CLASS_BITS: u32 = 7;
+
+/// Number of bits `PackedPitch` reserves for the octave, shifted above the
+/// class bits.
+const PACKED_OCTAVE_BITS: u32 = 4;
+
+/// Number of bits the spelling (class + octave) occupies, so the height key
+/// can be shifted above it.
+const PACKED_SPELLING_BITS: u32 = PACKED_CLASS_BITS + PACKED_OCTAVE_BITS;
+
+/// Bias added to the height key so it never goes negative (the lowest
+/// possible pitch, a double-flatted C in `Octave_`, nets out to -4).
+const HEIGHT_BIAS: i32 = 4;
+
+/// A `(PitchClass, PitchOctave)` packed into a single integer, for
+/// note-heavy structures that want fast comparison and more compact storage
+/// than the struct-plus-enum pair.  Bit layout, low to high: accidental (4
+/// bits) and letter name (3 bits), octave (4 bits), then a quarter-tone
+/// pitch-height key — so ascending integer order matches ascending pitch
+/// height across letters, octaves, and enharmonic spellings (spellings that
+/// sound at the same height, like `C#4`/`Db4`, tie on height and compare by
+/// their spelling bits instead, so they remain distinct but adjacent).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PackedPitch(pub u32);
+
+impl PackedPitch {
+    /// Pack a pitch class and octave.
+    pub fn new(class: PitchClass, octave: PitchOctave) -> PackedPitch {
+        let class_bits = u32::from(class.to_packed());
+        let octave_bits = u32::from(octave.to_packed());
+        let spelling = (octave_bits << PACKED_CLASS_BITS) | class_bits;
+
+        let height_bits = u32::from(height_key(&class, octave));
+
+        PackedPitch((height_bits << PACKED_SPELLING_BITS) | spelling)
+    }
+
+    /// Unpack back into a pitch class and octave.
+    pub fn to_pitch(&self) -> (PitchClass, PitchOctave) {
+        (self.pitch_class(), self.octave())
+    }
+
+    /// Letter name, masked and shifted out of the packed bits.
+    pub fn name(&self) -> PitchName {
+        self.pitch_class().name
+    }
+
+    /// Accidental, masked and shifted out of the packed bits.
+    pub fn accidental(&self) -> Option<PitchAccidental> {
+        self.pitch_class().accidental
+    }
+
+    /// Octave, masked and shifted out of the packed bits.
+    pub fn octave(&self) -> PitchOctave {
+        let bits = ((self.0 >> PACKED_CLASS_BITS) & ((1 << PACKED_OCTAVE_BITS) - 1)) as u8;
+
+        PitchOctave::from_packed(bits).unwrap_or(PitchOctave::Octave4)
+    }
+
+    fn pitch_class(&self) -> PitchClass {
+        let bits = (self.0 & ((1 << PACKED_CLASS_BITS) - 1)) as u8;
+
+        PitchClass::from_packed(bits)
+    }
+}
+
+/// Quarter-tone pitch height (semitone offset from C, doubled to keep
+/// quarter-tone accidentals exact, plus an octave term), biased to fit a
+/// non-negative `u16`.  Used only to order [`PackedPitch`]es by ear, not to
+/// recover the original spelling.
+fn height_key(class: &PitchClass, octave: PitchOctave) -> u16 {
+    let doubled_semitone = (class.semitone_offset() * 2.0) as i32;
+    let octave_term = (i32::from(octave as i8) + 1) * 24;
+
+    (doubled_semitone + octave_term + HEIGHT_BIAS) as u16
+}
+
+impl From<(PitchClass, PitchOctave)> for PackedPitch {
+    fn from((class, octave): (PitchClass, PitchOctave)) -> PackedPitch {
+        PackedPitch::new(class, octave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_pitch_roundtrip() {
+        let pitches = [
+            (PitchClass { name: PitchName::C, accidental: None }, PitchOctave::Octave4),
+            (PitchClass { name: PitchName::G, accidental: Some(PitchAccidental::Sharp) }, PitchOctave::Octave_),
+            (PitchClass { name: PitchName::B, accidental: Some(PitchAccidental::DoubleFlat) }, PitchOctave::Octave9),
+        ];
+
+        for (class, octave) in pitches.iter().copied() {
+            let packed = PackedPitch::new(class, octave);
+            assert_eq!(packed.name() as u8, class.name as u8);
+            assert_eq!(packed.accidental().map(|a| a.index()), class.accidental.map(|a| a.index()));
+            assert_eq!(packed.octave() as i8, octave as i8);
+        }
+    }
+
+    #[test]
+    fn packed_pitch_orders_by_height_within_a_letter() {
+        let low = PackedPitch::new(PitchClass { name: PitchName::C, accidental: None }, PitchOctave::Octave4);
+        let high = PackedPitch::new(PitchClass { name: PitchName::C, accidental: Some(PitchAccidental::Sharp) }, PitchOctave::Octave4);
+
+        assert!(low < high);
+    }
+
+    #[test]
+    fn packed_pitch_orders_by_height_across_letters_and_octaves() {
+        // Cb4 (MIDI 59) sounds lower than B#3 (MIDI 60), even though "B" (a
+        // later letter) nominally falls in the earlier octave.
+        let cb4 = PackedPitch::new(PitchClass { name: PitchName::C, accidental: Some(PitchAccidental::Flat) }, PitchOctave::Octave4);
+        let bsharp3 = PackedPitch::new(PitchClass { name: PitchName::B, accidental: Some(PitchAccidental::Sharp) }, PitchOctave::Octave3);
+
+        assert!(cb4 < bsharp3);
+    }
+
+    #[test]
+    fn midi_roundtrip() {
+        for n in 0..=127u8 {
+            let (class, octave) = PitchClass::from_midi(n);
+            assert_eq!(class.to_midi(octave, false), Some(n));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Resolves a [`Movement`] into a flat list of timed [`Event`]s, one
+//! channel after another and onset-ordered within each channel —
+//! dynamics, cresc/dim spans, swing, and grace notes all folded in.  The
+//! synth (`render.rs`) and MIDI export (`midi.rs`) don't use it; they walk
+//! the bars and interpret the markings themselves.
+//!
+//! Bars are walked in [`Movement::unfold`] order, so repeats, voltas, and
+//! `DC`/`DS`/`Fine`/`ToCoda` jumps are already resolved.
+
+use crate::{Dynamic, Fraction, Marking, Movement, PitchClass, PitchOctave};
+
+/// A single performed note (or grace note, with `dur` zero).
+#[derive(Clone)]
+pub struct Event {
+    /// Channel this event belongs to.
+    pub chan: usize,
+    /// Onset, as a fraction of a whole note from the start of the movement.
+    pub start: Fraction,
+    /// Duration, as a fraction of a whole note.
+    pub dur: Fraction,
+    /// Pitch & octave, or `None` for a rest.
+    pub pitch: Option<(PitchClass, PitchOctave)>,
+    /// Loudness, 0-1.
+    pub velocity: f32,
+}
+
+/// Perform every channel of `movement`'s unfolded bar order into a flat
+/// list of events (not necessarily sorted across channels).
+pub fn perform(movement: &Movement) -> Vec<Event> {
+    let order = movement.unfold();
+    let num_chans = movement.bar.get(0).map(|bar| bar.chan.len()).unwrap_or(0);
+
+    let mut events = Vec::new();
+    for c in 0..num_chans {
+        events.extend(perform_channel(movement, &order, c));
+    }
+    events
+}
+
+fn perform_channel(movement: &Movement, order: &[usize], c: usize) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut pos = Fraction::new(0, 1);
+    let mut velocity = Dynamic::MF.velocity() as f32 / 127.0;
+    let mut current_swing: u8 = 50;
+
+    // A pending cresc/dim span: the velocity it started from, the index of
+    // its first event, and the position it started at.
+    let mut span: Option<(f32, usize, Fraction)> = None;
+
+    for &bar_index in order {
+        let bar = match movement.bar.get(bar_index) {
+            Some(bar) => bar,
+            None => continue,
+        };
+        if let Some(sig_index) = bar.sig {
+            if let Some(sig) = movement.sig.get(sig_index as usize) {
+                current_swing = sig.swing.unwrap_or(50);
+            }
+        }
+        let chan = match bar.chan.get(c) {
+            Some(chan) => chan,
+            None => continue,
+        };
+
+        for marking_str in notes(chan) {
+            let marking: Marking = match marking_str.parse() {
+                Ok(marking) => marking,
+                Err(_) => continue,
+            };
+
+            match marking {
+                Marking::Dynamic(dynamic) => {
+                    let target = dynamic.velocity() as f32 / 127.0;
+                    if let Some((start_velocity, first, span_start)) = span.take() {
+                        interpolate_span(&mut events, first, start_velocity, target, span_start, pos);
+                    }
+                    velocity = target;
+                }
+                Marking::Cresc | Marking::Dim => {
+                    if span.is_none() {
+                        span = Some((velocity, events.len(), pos));
+                    }
+                }
+                Marking::Note(note) => {
+                    let dur = note.duration;
+                    events.push(Event { chan: c, start: pos, dur, pitch: note.pitch, velocity });
+                    swing_pair(&mut events, current_swing);
+                    pos = pos + dur;
+                }
+                // A grace note steals no time of its own: it sounds at the
+                // position it's written, but doesn't advance `pos`.
+                Marking::GraceInto(note) | Marking::GraceOutOf(note) => {
+                    events.push(Event { chan: c, start: pos, dur: Fraction::new(0, 1), pitch: note.pitch, velocity });
+                }
+                _ => {} // Breath/caesura/pizz/arco/mute/open carry no timing of their own.
+            }
+        }
+    }
+
+    if let Some((start_velocity, first, span_start)) = span {
+        interpolate_span(&mut events, first, start_velocity, velocity, span_start, pos);
+    }
+
+    events
+}
+
+/// Rewrites the velocities of `events[first..]` to linearly interpolate
+/// from `start_velocity` to `target_velocity` across `[span_start,
+/// span_end)`, by each event's onset position within that range.
+fn interpolate_span(events: &mut [Event], first: usize, start_velocity: f32, target_velocity: f32, span_start: Fraction, span_end: Fraction) {
+    let total = as_f32(span_end) - as_f32(span_start);
+
+    for event in &mut events[first..] {
+        let t = if total > 0.0 { ((as_f32(event.start) - as_f32(span_start)) / total).clamp(0.0, 1.0) } else { 0.0 };
+        event.velocity = start_velocity + (target_velocity - start_velocity) * t;
+    }
+}
+
+fn as_f32(fraction: Fraction) -> f32 {
+    f32::from(fraction.num) / f32::from(fraction.den)
+}
+
+/// If the last two events just pushed form an on-beat pair of eighth
+/// notes, redistributes their combined duration per `swing_percent` (50 =
+/// even, no change; 66 = the first gets 66% of the pair, close to the 2/3
+/// of triplet swing).
+fn swing_pair(events: &mut [Event], swing_percent: u8) {
+    if swing_percent == 50 {
+        return;
+    }
+    let len = events.len();
+    if len < 2 {
+        return;
+    }
+
+    let (rest, last) = events.split_at_mut(len - 1);
+    let first = &mut rest[len - 2];
+    let second = &mut last[0];
+
+    let eighth = Fraction::new(1, 8);
+    if first.dur != eighth || second.dur != eighth {
+        return;
+    }
+    if second.start != first.start + first.dur {
+        return;
+    }
+    if !on_beat(first.start) {
+        return;
+    }
+
+    let pair = first.dur + second.dur;
+
+    // Split at the nearest 200th of a whole note rather than the literal
+    // `swing_percent`/100 ratio: for some percentages (e.g. 67) that
+    // ratio's reduced denominator doesn't fit a `u8`.  `pair` is always
+    // exactly 1/4 here (two eighth notes), i.e. 50/200, so this gives the
+    // exact split for every even percentage and rounds an odd one up to
+    // the next even percentage (67 splits as 68), rather than skipping it.
+    let two_hundredths = (f32::from(swing_percent) / 2.0).round() as u8;
+    let new_first = Fraction::new(two_hundredths, 200).reduce();
+    let new_second = match pair.checked_sub(new_first) {
+        Some(fraction) => fraction,
+        None => return,
+    };
+
+    first.dur = new_first;
+    second.start = first.start + new_first;
+    second.dur = new_second;
+}
+
+/// Whether `pos` falls on a quarter-note (beat) boundary.
+fn on_beat(pos: Fraction) -> bool {
+    4 % u32::from(pos.reduce().den) == 0
+}
+
+/// Notes for a channel (shared accessor, since `Chan::notes` is private).
+fn notes(chan: &crate::Chan) -> &[String] {
+    &chan.notes
+}
@@ -0,0 +1,237 @@
+//! Software synthesizer: renders a [`Movement`] to a buffer of PCM samples.
+//!
+//! This walks each [`Bar`]'s [`Chan`]s in order, resolves each note's
+//! duration against the bar's current `Sig` (tempo in BPM, beat = quarter
+//! note), and synthesizes it with a simple oscillator wrapped in an
+//! attack-decay-sustain-release envelope.  There's no link in the data
+//! model from a channel to a concrete [`Waveform`] (`SynthChan::waveform`
+//! only stores names).  `render_note` can play a waveform table, but
+//! [`render_movement`] has no way to receive one and always renders with
+//! the sine-wave fallback.
+
+use crate::{Bar, Chan, Instrument, Movement, Note, Synth, Waveform};
+
+/// Standard (CD-quality) sample rate to pass to
+/// [`Scof::render`](crate::Scof::render), which takes the rate explicitly.
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+// Envelope defaults, used when an `Instrument` doesn't specify its own.
+const DEFAULT_ATTACK: f32 = 0.01;
+const DEFAULT_DECAY: f32 = 0.08;
+const DEFAULT_SUSTAIN: f32 = 0.75;
+const DEFAULT_RELEASE: f32 = 0.15;
+
+impl Instrument {
+    /// Attack time in seconds (default 0.01).
+    fn attack_secs(&self) -> f32 {
+        self.attack.unwrap_or(DEFAULT_ATTACK)
+    }
+
+    /// Decay time in seconds (default 0.08).
+    fn decay_secs(&self) -> f32 {
+        self.decay.unwrap_or(DEFAULT_DECAY)
+    }
+
+    /// Sustain level, 0-1 (default 0.75).
+    fn sustain_level(&self) -> f32 {
+        self.sustain.unwrap_or(DEFAULT_SUSTAIN)
+    }
+
+    /// Release time in seconds (default 0.15).
+    fn release_secs(&self) -> f32 {
+        self.release.unwrap_or(DEFAULT_RELEASE)
+    }
+}
+
+impl Waveform {
+    /// Decode the hexadecimal `wave` string into signed samples.
+    fn samples(&self) -> Vec<i16> {
+        let bytes: Vec<u8> = (0..self.wave.len())
+            .step_by(2)
+            .filter_map(|i| self.wave.get(i..i + 2))
+            .filter_map(|byte| u8::from_str_radix(byte, 16).ok())
+            .collect();
+
+        if self.si16 {
+            bytes.chunks_exact(2).map(|c| i16::from_be_bytes([c[0], c[1]])).collect()
+        } else {
+            bytes.iter().map(|&b| i16::from(b as i8) * 256).collect()
+        }
+    }
+
+    /// Sample the waveform table at `phase` (0-1, wrapping), in the range
+    /// -1.0 to 1.0.
+    fn sample_at(&self, phase: f32) -> f32 {
+        let samples = self.samples();
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        let index = (phase.rem_euclid(1.0) * samples.len() as f32) as usize;
+        f32::from(samples[index.min(samples.len() - 1)]) / f32::from(i16::MAX)
+    }
+}
+
+/// Gain of the attack-decay-sustain-release envelope at sample `i` of
+/// `nb_samples` total.
+fn envelope_gain(i: usize, nb_samples: usize, sample_rate: u32, instrument: &Instrument) -> f32 {
+    let attack = (instrument.attack_secs() * sample_rate as f32) as usize;
+    let decay = (instrument.decay_secs() * sample_rate as f32) as usize;
+    let release = (instrument.release_secs() * sample_rate as f32) as usize;
+    let sustain = instrument.sustain_level();
+
+    if i < attack {
+        i as f32 / attack.max(1) as f32
+    } else if i < attack + decay {
+        let t = (i - attack) as f32 / decay.max(1) as f32;
+        1.0 + (sustain - 1.0) * t
+    } else if i + release >= nb_samples {
+        let remaining = nb_samples.saturating_sub(i);
+        sustain * remaining as f32 / release.max(1) as f32
+    } else {
+        sustain
+    }
+}
+
+/// Shared, read-only context for rendering a movement.
+struct RenderContext<'a> {
+    synth: &'a Synth,
+    soundfont: &'a [Instrument],
+    default_instrument: Instrument,
+    concert_a: f64,
+    sample_rate: u32,
+}
+
+impl<'a> RenderContext<'a> {
+    /// Instrument assigned to channel `c`, falling back to the first
+    /// instrument in the soundfont, or a default instrument if there is none.
+    fn instrument(&self, c: usize) -> &Instrument {
+        self.soundfont.get(c).or_else(|| self.soundfont.get(0)).unwrap_or(&self.default_instrument)
+    }
+
+    /// Volume (0-1) of channel `c` (default 1.0).
+    fn volume(&self, c: usize) -> f32 {
+        self.synth.chan.get(c).map(|chan| chan.volume).unwrap_or(1.0)
+    }
+}
+
+/// Render a single note to `f32` samples in the range -1.0 to 1.0.  Rests
+/// render as silence.
+fn render_note(
+    note: &Note,
+    instrument: &Instrument,
+    waveform: Option<&Waveform>,
+    volume: f32,
+    tempo: u16,
+    ctx: &RenderContext,
+) -> Vec<f32> {
+    let tempo = if tempo == 0 { 120.0 } else { f64::from(tempo) };
+    // `duration` is a fraction of a whole note; a whole note is 4 beats.
+    let duration_secs = f64::from(note.duration.num) / f64::from(note.duration.den) * 4.0 * 60.0 / tempo;
+    let nb_samples = (duration_secs * f64::from(ctx.sample_rate)) as usize;
+
+    let mut out = vec![0.0f32; nb_samples];
+
+    let frequency = match note.frequency(ctx.concert_a) {
+        Some(frequency) => frequency as f32,
+        None => return out, // Rest.
+    };
+
+    for (i, out) in out.iter_mut().enumerate() {
+        let t = i as f32 / ctx.sample_rate as f32;
+        let phase = t * frequency;
+
+        let osc = match waveform {
+            Some(waveform) if waveform.once => {
+                // Not pitched: play the sample table once over the note.
+                waveform.sample_at(i as f32 / nb_samples.max(1) as f32)
+            }
+            Some(waveform) => waveform.sample_at(phase),
+            None => (phase.fract() * 2.0 * std::f32::consts::PI).sin(),
+        };
+
+        *out = osc * envelope_gain(i, nb_samples, ctx.sample_rate, instrument) * volume;
+    }
+
+    out
+}
+
+/// Render a movement to a buffer of 16-bit PCM samples at `sample_rate`,
+/// mixing down every channel.  `soundfont` provides one [`Instrument`] per
+/// channel (the first instrument is used as a fallback for channels beyond
+/// its length); `concert_a` is the frequency of A4 in Hz (440.0 is standard).
+pub fn render_movement(
+    movement: &Movement,
+    synth: &Synth,
+    soundfont: &[Instrument],
+    concert_a: f64,
+    sample_rate: u32,
+) -> Vec<i16> {
+    let ctx = RenderContext {
+        synth,
+        soundfont,
+        default_instrument: Instrument::default(),
+        concert_a,
+        sample_rate,
+    };
+
+    let num_chans = movement.bar.get(0).map(|bar| bar.chan.len()).unwrap_or(0);
+    let mut chan_buffers: Vec<Vec<f32>> = vec![vec![]; num_chans];
+
+    let mut current_sig = 0usize;
+    for bar in &movement.bar {
+        if let Some(sig_index) = bar.sig {
+            current_sig = sig_index as usize;
+        }
+        let tempo = movement.sig.get(current_sig).map(|sig| sig.tempo).unwrap_or(0);
+
+        render_bar(bar, tempo, &ctx, &mut chan_buffers);
+    }
+
+    mix_down(chan_buffers)
+}
+
+/// Render one bar's worth of notes into `chan_buffers`, one entry per
+/// channel, appending as it goes.
+fn render_bar(bar: &Bar, tempo: u16, ctx: &RenderContext, chan_buffers: &mut Vec<Vec<f32>>) {
+    for (c, chan) in bar.chan.iter().enumerate() {
+        let volume = ctx.volume(c);
+        let instrument = ctx.instrument(c);
+
+        let buf = match chan_buffers.get_mut(c) {
+            Some(buf) => buf,
+            None => continue,
+        };
+
+        for note_str in notes(chan) {
+            let note: Note = match note_str.parse() {
+                Ok(note) => note,
+                Err(_) => continue,
+            };
+
+            buf.extend(render_note(&note, instrument, None, volume, tempo, ctx));
+        }
+    }
+}
+
+/// Notes for a channel (shared accessor, since `Chan::notes` is private).
+fn notes(chan: &Chan) -> &[String] {
+    &chan.notes
+}
+
+/// Sum channel buffers sample-by-sample and convert to 16-bit PCM, clipping
+/// to the valid range.
+fn mix_down(chan_buffers: Vec<Vec<f32>>) -> Vec<i16> {
+    let total_len = chan_buffers.iter().map(|buf| buf.len()).max().unwrap_or(0);
+    let mut mix = vec![0.0f32; total_len];
+
+    for buf in &chan_buffers {
+        for (i, sample) in buf.iter().enumerate() {
+            mix[i] += sample;
+        }
+    }
+
+    mix.into_iter()
+        .map(|sample| (sample.max(-1.0).min(1.0) * f32::from(i16::MAX)) as i16)
+        .collect()
+}
@@ -0,0 +1,122 @@
+//! Turns a [`Movement`]'s `Repeat` markings into a playable bar order.
+//! `perform` walks bars in this order; the synth and MIDI exporters walk
+//! the raw bar list.
+
+use std::collections::HashMap;
+
+use crate::{Bar, Movement, Repeat};
+
+/// How many times each `:|` jumps back to its `|:` (once, so the section
+/// plays twice) before playing straight through; this cap also keeps a
+/// repeat from looping forever.  A `:|` with no open `|:` never jumps.
+const MAX_TAKEN: u32 = 1;
+
+impl Movement {
+    /// The sequence of bar indices to play, once `Open`/`Close` repeats,
+    /// numbered `Ending` brackets, and `DC`/`DS`/`Fine`/`ToCoda`/`Coda` jumps
+    /// are all resolved.
+    pub fn unfold(&self) -> Vec<usize> {
+        let mut order = Vec::new();
+        let mut open_stack: Vec<usize> = Vec::new();
+        let mut taken: HashMap<usize, u32> = HashMap::new();
+        let mut pass: HashMap<usize, u32> = HashMap::new();
+        let mut segno_bar = None;
+        let mut coda_bar = None;
+
+        let mut i = 0;
+        while i < self.bar.len() {
+            let repeats = repeats(&self.bar[i]);
+
+            if repeats.iter().any(|r| matches!(r, Repeat::Segno)) {
+                segno_bar = Some(i);
+            }
+            if repeats.iter().any(|r| matches!(r, Repeat::Coda)) {
+                coda_bar = Some(i);
+            }
+
+            let current_pass = open_stack.last().map(|start| pass[start]).unwrap_or(1);
+            if let Some(ending) = repeats.iter().find_map(|r| match r {
+                Repeat::Ending(n) => Some(*n),
+                _ => None,
+            }) {
+                if u32::from(ending) != current_pass {
+                    i += 1;
+                    continue;
+                }
+            }
+
+            order.push(i);
+
+            if repeats.iter().any(|r| matches!(r, Repeat::Open)) && open_stack.last() != Some(&i) {
+                open_stack.push(i);
+                pass.entry(i).or_insert(1);
+            }
+
+            if repeats.iter().any(|r| matches!(r, Repeat::Close)) {
+                if let Some(&start) = open_stack.last() {
+                    if *taken.get(&start).unwrap_or(&0) < MAX_TAKEN {
+                        *taken.entry(start).or_insert(0) += 1;
+                        *pass.entry(start).or_insert(1) += 1;
+                        i = start;
+                        continue;
+                    }
+                    open_stack.pop();
+                }
+            }
+
+            i += 1;
+        }
+
+        append_dc_ds(self, &mut order, segno_bar, coda_bar);
+        order
+    }
+}
+
+/// Appends the `D.C.`/`D.S.` run (if the movement has one) to `order`:
+/// from bar 0 for `DC`, from the `Segno` bar for `DS`, stopping at `Fine`
+/// or jumping from `ToCoda` to the `Coda` bar.
+fn append_dc_ds(movement: &Movement, order: &mut Vec<usize>, segno_bar: Option<usize>, coda_bar: Option<usize>) {
+    let is_dc = movement.bar.iter().any(|bar| repeats(bar).iter().any(|r| matches!(r, Repeat::DC)));
+    let is_ds = movement.bar.iter().any(|bar| repeats(bar).iter().any(|r| matches!(r, Repeat::DS)));
+
+    let start = if is_dc {
+        0
+    } else if is_ds {
+        match segno_bar {
+            Some(start) => start,
+            None => return,
+        }
+    } else {
+        return;
+    };
+
+    let mut j = start;
+    let mut jumped_to_coda = false;
+    while j < movement.bar.len() {
+        let repeats = repeats(&movement.bar[j]);
+
+        if !jumped_to_coda && repeats.iter().any(|r| matches!(r, Repeat::ToCoda)) {
+            order.push(j);
+            match coda_bar {
+                Some(coda) => {
+                    j = coda;
+                    jumped_to_coda = true;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        order.push(j);
+
+        if repeats.iter().any(|r| matches!(r, Repeat::Fine)) {
+            break;
+        }
+
+        j += 1;
+    }
+}
+
+fn repeats(bar: &Bar) -> Vec<Repeat> {
+    bar.repeat.iter().filter_map(|s| s.parse().ok()).collect()
+}